@@ -0,0 +1,97 @@
+//! GhostCell-style interior mutability, where aliasing-XOR-mutability is
+//! enforced by a single retained [`Guard`], rather than by each cell.
+//!
+//! ```rust
+//! use generativity::{cell::BrandedCell, make_guard};
+//! make_guard!(guard);
+//! let mut guard = guard;
+//! let cell = BrandedCell::new(0, &guard);
+//! *cell.borrow_mut(&mut guard) += 1;
+//! assert_eq!(*cell.borrow(&guard), 1);
+//! ```
+
+use crate::{Guard, Id};
+use core_::cell::UnsafeCell;
+
+/// A cell providing interior mutability, unlocked by a [`Guard<'id>`]
+/// instead of a runtime flag or lock.
+///
+/// All cells sharing a brand `'id` are unlocked by that one `Guard<'id>`, so
+/// borrowing the guard is what the borrow checker actually tracks:
+/// [`borrow`](Self::borrow) takes `&Guard<'id>`, [`borrow_mut`](Self::borrow_mut)
+/// takes `&mut Guard<'id>`, and ordinary borrow-checking on the guard is
+/// enough to rule out aliased mutation across the whole family of cells.
+///
+/// This only works while the `Guard` itself is kept around as a token,
+/// rather than consumed `into` an [`Id`] right away; see [`BrandedCell::new`].
+#[repr(transparent)]
+pub struct BrandedCell<'id, T> {
+    value: UnsafeCell<T>,
+    id: Id<'id>,
+}
+
+// SAFETY: `Guard` is `Sync`, so two threads can both hold `&Guard<'id>` and
+// both call `borrow` at once, each getting a `&T` live on a different thread
+// at the same time. That's the `RwLock`-reader shape, not the `Mutex` shape:
+// it requires `T: Sync`, same as `RwLock<T>: Sync` does, in addition to
+// `T: Send` for values moved here by a `borrow_mut` from another thread.
+unsafe impl<'id, T> Sync for BrandedCell<'id, T> where T: Send + Sync {}
+
+impl<'id, T> BrandedCell<'id, T> {
+    /// Construct a new `BrandedCell`, brought into `guard`'s brand.
+    ///
+    /// Keep `guard` alive and retained (rather than converting it `into` an
+    /// [`Id`]) for as long as you need to [`borrow`](Self::borrow) or
+    /// [`borrow_mut`](Self::borrow_mut) this cell, or any other
+    /// `BrandedCell` sharing the same brand: it is the single token that
+    /// proves aliasing-XOR-mutability across the whole family of cells.
+    pub fn new(value: T, guard: &Guard<'id>) -> Self {
+        BrandedCell {
+            value: UnsafeCell::new(value),
+            id: guard.id(),
+        }
+    }
+
+    /// Borrow the contents of the cell immutably, unlocked by `guard`.
+    pub fn borrow<'a>(&'a self, _guard: &'a Guard<'id>) -> &'a T {
+        // SAFETY: `_guard` carries the same brand as this cell, and is
+        // borrowed immutably, so no `&mut Guard<'id>` (and thus no
+        // `borrow_mut` of any cell sharing this brand) can be live.
+        unsafe { &*self.value.get() }
+    }
+
+    /// Borrow the contents of the cell mutably, unlocked by `guard`.
+    pub fn borrow_mut<'a>(&'a self, _guard: &'a mut Guard<'id>) -> &'a mut T {
+        // SAFETY: `_guard` carries the same brand as this cell, and is
+        // borrowed mutably, so this is the only live borrow of any cell
+        // sharing this brand.
+        unsafe { &mut *self.value.get() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::make_guard;
+
+    #[test]
+    fn borrow_and_borrow_mut_round_trip() {
+        make_guard!(guard);
+        let mut guard = guard;
+        let cell = BrandedCell::new(0, &guard);
+        *cell.borrow_mut(&mut guard) += 1;
+        assert_eq!(*cell.borrow(&guard), 1);
+    }
+
+    #[test]
+    fn two_cells_share_one_guard() {
+        make_guard!(guard);
+        let mut guard = guard;
+        let a = BrandedCell::new(1, &guard);
+        let b = BrandedCell::new(2, &guard);
+        *a.borrow_mut(&mut guard) += 10;
+        *b.borrow_mut(&mut guard) += 20;
+        assert_eq!(*a.borrow(&guard), 11);
+        assert_eq!(*b.borrow(&guard), 22);
+    }
+}
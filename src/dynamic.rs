@@ -0,0 +1,177 @@
+//! Runtime-tagged brands, for passing a brand across a boundary that only
+//! understands plain integers, such as an FFI callback that hands a handle
+//! back to you later in the same process.
+//!
+//! A [`DynGuard`] (made with [`make_guard_dyn!`]) pairs a [`Guard`] with a
+//! `u64` tag, unique among tags minted so far in this process. The tag alone
+//! is just an untrusted integer, but [`try_rebrand`] turns it back into a
+//! trusted [`Id`], given a live `DynGuard` to check it against.
+//!
+//! The counter backing these tags restarts at zero every run, so a tag is
+//! only meaningful within the process that minted it: don't persist one
+//! across a process restart and expect it to still identify the same brand.
+//!
+//! ```rust
+//! use generativity::{dynamic::try_rebrand, make_guard_dyn};
+//! make_guard_dyn!(guard);
+//! let tag = guard.tag();
+//!
+//! // ... `tag` crosses an FFI boundary and comes back later ...
+//!
+//! let id = unsafe { try_rebrand(tag, &guard) };
+//! assert!(id.is_some());
+//! assert!(unsafe { try_rebrand(tag.wrapping_add(1), &guard) }.is_none());
+//! ```
+
+use crate::{Guard, Id};
+use core_::fmt;
+use core_::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_TAG: AtomicU64 = AtomicU64::new(0);
+
+/// Mint a fresh tag, unique among all tags minted so far in this process.
+///
+/// Panics rather than wrapping on overflow, so a tag is never reused.
+fn next_tag() -> u64 {
+    NEXT_TAG
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |tag| {
+            tag.checked_add(1)
+        })
+        .expect("generativity: DynId tag counter overflowed")
+}
+
+/// A [`Guard<'id>`] paired with a process-unique runtime tag.
+///
+/// Use [`make_guard_dyn!`] to construct one. Converts `into` a [`DynId`] to
+/// be stored in structures, the same way a plain [`Guard`] converts `into`
+/// an [`Id`].
+#[derive(Eq, PartialEq)]
+pub struct DynGuard<'id> {
+    guard: Guard<'id>,
+    tag: u64,
+}
+
+impl<'id> DynGuard<'id> {
+    #[doc(hidden)]
+    /// NOT STABLE PUBLIC API. Used by the expansion of [`make_guard_dyn!`].
+    pub fn new(guard: Guard<'id>) -> Self {
+        DynGuard {
+            guard,
+            tag: next_tag(),
+        }
+    }
+
+    /// This brand's process-unique runtime tag.
+    pub fn tag(&self) -> u64 {
+        self.tag
+    }
+}
+
+impl<'id> fmt::Debug for DynGuard<'id> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("#[unique] 'id")
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+impl<'id> From<DynGuard<'id>> for DynId<'id> {
+    fn from(guard: DynGuard<'id>) -> Self {
+        DynId {
+            id: guard.guard.into(),
+            tag: guard.tag,
+        }
+    }
+}
+
+/// An [`Id<'id>`] paired with the process-unique runtime tag minted for its
+/// brand by [`make_guard_dyn!`].
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct DynId<'id> {
+    id: Id<'id>,
+    tag: u64,
+}
+
+impl<'id> DynId<'id> {
+    /// This brand's process-unique runtime tag.
+    pub fn tag(&self) -> u64 {
+        self.tag
+    }
+}
+
+impl<'id> From<DynId<'id>> for Id<'id> {
+    fn from(dyn_id: DynId<'id>) -> Self {
+        dyn_id.id
+    }
+}
+
+impl<'id> fmt::Debug for DynId<'id> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("#[invariant] 'id")
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+/// Create a `DynGuard` with a unique invariant lifetime and a fresh,
+/// process-unique runtime tag.
+///
+/// Otherwise behaves exactly like [`make_guard!`](crate::make_guard!).
+#[macro_export]
+macro_rules! make_guard_dyn {
+    ($name:ident) => {
+        $crate::make_guard!($name);
+        let $name = $crate::dynamic::DynGuard::new($name);
+    };
+}
+
+/// Attempt to turn an untrusted `raw_tag` back into a trusted `Id<'id>`.
+///
+/// Returns `Some` only if `raw_tag` equals `guard`'s own tag.
+///
+/// # Safety
+///
+/// Only pass a `raw_tag` that came from a prior call to
+/// [`guard.tag()`](DynGuard::tag); this function trusts that match to mean
+/// the integer really does identify `'id`, and has no other way to check.
+pub unsafe fn try_rebrand<'id>(raw_tag: u64, guard: &DynGuard<'id>) -> Option<Id<'id>> {
+    if raw_tag == guard.tag {
+        Some(guard.guard.id())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn try_rebrand_matching_tag() {
+        make_guard_dyn!(guard);
+        let tag = guard.tag();
+        assert!(unsafe { try_rebrand(tag, &guard) }.is_some());
+    }
+
+    #[test]
+    fn try_rebrand_rejects_mismatched_tag() {
+        make_guard_dyn!(guard);
+        let other_tag = guard.tag().wrapping_add(1);
+        assert!(unsafe { try_rebrand(other_tag, &guard) }.is_none());
+    }
+
+    #[test]
+    fn distinct_guards_get_distinct_tags() {
+        make_guard_dyn!(a);
+        make_guard_dyn!(b);
+        assert_ne!(a.tag(), b.tag());
+    }
+
+    #[test]
+    fn dyn_id_keeps_the_tag() {
+        make_guard_dyn!(guard);
+        let tag = guard.tag();
+        let id: DynId<'_> = guard.into();
+        assert_eq!(id.tag(), tag);
+    }
+}
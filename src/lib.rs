@@ -15,6 +15,13 @@
 //! Struct(a.into());
 //! ```
 //!
+//! [`with_guard`] is an expression-position alternative to [`make_guard!`]
+//! for when a `let`-binding macro is awkward. A few modules build on top of
+//! brands for common use cases: [`cell`] for `Guard`-gated interior
+//! mutability, [`vec`] for bounds-check-free branded indexing (requires the
+//! `alloc` feature), and [`dynamic`] for round-tripping a brand through a
+//! raw integer within the same process (requires the `atomic64` feature).
+//!
 //! This is the concept of "generative" lifetime brands. `Guard` and `Id` are
 //! [invariant](https://doc.rust-lang.org/nomicon/subtyping.html#variance) over
 //! their lifetime parameter, meaning that it is never valid to substitute or
@@ -40,9 +47,18 @@
 //! only indicates invariance, whereas `&mut T` can carry further implication
 //! of "by example" use of `PhantomData`.
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 use core_::fmt;
 use core_::marker::PhantomData;
 
+pub mod cell;
+#[cfg(feature = "atomic64")]
+pub mod dynamic;
+#[cfg(feature = "alloc")]
+pub mod vec;
+
 #[doc(hidden)]
 /// NOT STABLE PUBLIC API. Previously Used by the expansion of [`make_guard!`].
 pub extern crate core as core_;
@@ -99,7 +115,6 @@ impl<'id> From<Guard<'id>> for Id<'id> {
 #[repr(transparent)]
 #[derive(Eq, PartialEq)]
 pub struct Guard<'id> {
-    #[allow(unused)]
     id: Id<'id>,
 }
 
@@ -117,6 +132,12 @@ impl<'id> Guard<'id> {
     pub unsafe fn new(id: Id<'id>) -> Guard<'id> {
         Guard { id }
     }
+
+    /// Get a copy of the `Id` carrying this guard's brand, without giving up
+    /// the guard itself.
+    pub(crate) fn id(&self) -> Id<'id> {
+        self.id
+    }
 }
 
 impl<'id> fmt::Debug for Guard<'id> {
@@ -190,6 +211,36 @@ macro_rules! make_guard {
     };
 }
 
+/// Create a `Guard` with a unique invariant lifetime and pass it to `f`.
+///
+/// This is an expression-position alternative to [`make_guard!`], for use in
+/// combinator-style code or anywhere a `let`-binding macro is awkward.
+///
+/// ```rust
+/// use generativity::{Guard, with_guard};
+/// let debug = with_guard(|guard: Guard<'_>| format!("{:?}", guard));
+/// assert_eq!(debug, "#[unique] 'id");
+/// ```
+///
+/// Because `f` is `for<'id> FnOnce(Guard<'id>) -> R`, the compiler must
+/// instantiate a fresh, invariant `'id` for each call, and `R` is chosen by
+/// the caller outside of the `for<'id>` binder, so `R` cannot name `'id`:
+/// the brand provably cannot escape `f`. As with [`make_guard!`], two nested
+/// calls to `with_guard` produce brands that can never unify:
+///
+/// ```rust,compile_fail,E0597
+/// # use generativity::{with_guard, Guard};
+/// with_guard(|a: Guard<'_>| {
+///     with_guard(|b: Guard<'_>| {
+///         dbg!(a == b); // ERROR (here == is a static check)
+///     });
+/// });
+/// ```
+pub fn with_guard<R>(f: impl for<'id> FnOnce(Guard<'id>) -> R) -> R {
+    make_guard!(guard);
+    f(guard)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -203,6 +254,16 @@ mod test {
         assert_eq!(b, b);
     }
 
+    #[test]
+    fn with_guard_works() {
+        with_guard(|a| {
+            with_guard(|b| {
+                assert_eq!(a, a);
+                assert_eq!(b, b);
+            });
+        });
+    }
+
     #[test]
     fn test_oibits() {
         fn assert_oibits<T>(_: &T)
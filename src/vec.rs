@@ -0,0 +1,126 @@
+//! An append-only vector that hands out branded indices, so a handle
+//! obtained from one `BrandedVec` can never be used to index into another.
+//!
+//! ```rust
+//! use generativity::{make_guard, vec::BrandedVec};
+//! make_guard!(guard);
+//! let mut vec = BrandedVec::new(guard);
+//! let index = vec.push(42);
+//! assert_eq!(*vec.get(index), 42);
+//! ```
+
+use crate::{Guard, Id};
+use alloc::vec::Vec;
+use core_::fmt;
+
+/// A branded index into a [`BrandedVec<'id, T>`] sharing the same brand.
+///
+/// The only way to obtain an `Index<'id>` is from the [`BrandedVec::push`]
+/// of the vec carrying the same `'id`, so it can never be used to index
+/// into a different `BrandedVec`, even one storing the same element type.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Index<'id> {
+    raw: usize,
+    id: Id<'id>,
+}
+
+impl<'id> fmt::Debug for Index<'id> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Index").field("raw", &self.raw).finish()
+    }
+}
+
+/// An append-only `Vec<T>` that hands out indices branded with its `'id`.
+///
+/// A previously issued [`Index<'id>`] stays valid for as long as this vec
+/// does: nothing ever removes, reorders, or reuses a slot. That, plus the
+/// brand ruling out indices from any other vec, is what lets `get`/`get_mut`
+/// skip the bounds check.
+pub struct BrandedVec<'id, T> {
+    vec: Vec<T>,
+    id: Id<'id>,
+}
+
+impl<'id, T> BrandedVec<'id, T> {
+    /// Construct a new, empty `BrandedVec`, branded by `guard`.
+    pub fn new(guard: Guard<'id>) -> Self {
+        BrandedVec {
+            vec: Vec::new(),
+            id: guard.into(),
+        }
+    }
+
+    /// Append `value`, returning an `Index<'id>` that can be used to access
+    /// it through this same `BrandedVec` (via [`get`](Self::get) or
+    /// [`get_mut`](Self::get_mut)) for as long as this vec is alive.
+    pub fn push(&mut self, value: T) -> Index<'id> {
+        let raw = self.vec.len();
+        self.vec.push(value);
+        Index { raw, id: self.id }
+    }
+
+    /// The number of elements in the vec.
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Whether the vec contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Get a reference to the element at `index`.
+    pub fn get(&self, index: Index<'id>) -> &T {
+        // SAFETY: the shared `'id` brand proves `index` was issued by this
+        // exact vec's `push`, so `index.raw` is in bounds: this vec only
+        // grows, and never reorders or reuses a slot.
+        unsafe { self.vec.get_unchecked(index.raw) }
+    }
+
+    /// Get a mutable reference to the element at `index`.
+    ///
+    /// See [`get`](Self::get) for the invariant that makes this safe.
+    pub fn get_mut(&mut self, index: Index<'id>) -> &mut T {
+        // SAFETY: see `get`.
+        unsafe { self.vec.get_unchecked_mut(index.raw) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::make_guard;
+
+    #[test]
+    fn push_get_round_trip() {
+        make_guard!(guard);
+        let mut vec = BrandedVec::new(guard);
+        assert!(vec.is_empty());
+
+        let a = vec.push("a");
+        let b = vec.push("b");
+        assert_eq!(vec.len(), 2);
+        assert_eq!(*vec.get(a), "a");
+        assert_eq!(*vec.get(b), "b");
+    }
+
+    #[test]
+    fn get_mut_round_trip() {
+        make_guard!(guard);
+        let mut vec = BrandedVec::new(guard);
+        let i = vec.push(1);
+        *vec.get_mut(i) += 41;
+        assert_eq!(*vec.get(i), 42);
+    }
+
+    #[test]
+    fn index_survives_reallocation() {
+        make_guard!(guard);
+        let mut vec = BrandedVec::new(guard);
+        let first = vec.push(0);
+        for n in 1..1000 {
+            vec.push(n);
+        }
+        assert_eq!(*vec.get(first), 0);
+    }
+}